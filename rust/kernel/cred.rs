@@ -8,7 +8,9 @@
 
 use crate::{
     bindings,
-    task::Kuid,
+    error::Result,
+    security::SecurityCtx,
+    task::{Kgid, Kuid},
     types::{AlwaysRefCounted, Opaque},
 };
 
@@ -52,11 +54,52 @@ impl Credential {
         secid
     }
 
+    /// Returns the real UID of the given credential.
+    pub fn uid(&self) -> Kuid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kuid::from_raw(unsafe { (*self.0.get()).uid })
+    }
+
     /// Returns the effective UID of the given credential.
     pub fn euid(&self) -> Kuid {
         // SAFETY: By the type invariant, we know that `self.0` is valid.
         Kuid::from_raw(unsafe { (*self.0.get()).euid })
     }
+
+    /// Returns the saved UID of the given credential.
+    pub fn suid(&self) -> Kuid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kuid::from_raw(unsafe { (*self.0.get()).suid })
+    }
+
+    /// Returns the filesystem UID of the given credential.
+    pub fn fsuid(&self) -> Kuid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kuid::from_raw(unsafe { (*self.0.get()).fsuid })
+    }
+
+    /// Returns the real GID of the given credential.
+    pub fn gid(&self) -> Kgid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kgid::from_raw(unsafe { (*self.0.get()).gid })
+    }
+
+    /// Returns the effective GID of the given credential.
+    pub fn egid(&self) -> Kgid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kgid::from_raw(unsafe { (*self.0.get()).egid })
+    }
+
+    /// Returns the filesystem GID of the given credential.
+    pub fn fsgid(&self) -> Kgid {
+        // SAFETY: By the type invariant, we know that `self.0` is valid.
+        Kgid::from_raw(unsafe { (*self.0.get()).fsgid })
+    }
+
+    /// Get the security context given the credential's secid.
+    pub fn get_secctx(&self) -> Result<SecurityCtx> {
+        SecurityCtx::from_secid(self.get_secid())
+    }
 }
 
 // SAFETY: The type invariants guarantee that `Credential` is always ref-counted.
@@ -11,7 +11,7 @@ use crate::{
     error::{code::*, Error, Result},
     types::{ARef, AlwaysRefCounted, NotThreadSafe, Opaque},
 };
-use alloc::boxed::Box;
+use alloc::{boxed::Box, vec::Vec};
 use core::{alloc::AllocError, marker::PhantomData, mem, ptr};
 
 /// Flags associated with a [`File`].
@@ -179,6 +179,58 @@ impl File {
         // TODO: Replace with `read_once` when available on the Rust side.
         unsafe { core::ptr::addr_of!((*self.as_ptr()).f_flags).read_volatile() }
     }
+
+    /// Returns the current value of the file's position (`f_pos`).
+    pub fn pos(&self) -> u64 {
+        // This `read_volatile` is intended to correspond to a READ_ONCE call.
+        //
+        // SAFETY: The file is valid because the shared reference guarantees a nonzero refcount.
+        //
+        // TODO: Replace with `read_once` when available on the Rust side.
+        unsafe { core::ptr::addr_of!((*self.as_ptr()).f_pos).read_volatile() as u64 }
+    }
+
+    /// Reads from this file into `buf`, starting at `offset`, without changing `offset`'s
+    /// caller-tracked value between calls.
+    ///
+    /// Returns the number of bytes read.
+    pub fn read(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        if self.flags() & flags::O_ACCMODE == flags::O_WRONLY {
+            return Err(EBADF);
+        }
+
+        let mut pos = offset as bindings::loff_t;
+        // SAFETY: `self.as_ptr()` is valid because the shared reference guarantees a nonzero
+        // refcount, and `buf` is valid for writing `buf.len()` bytes.
+        let res = unsafe {
+            bindings::kernel_read(self.as_ptr(), buf.as_mut_ptr().cast(), buf.len(), &mut pos)
+        };
+        if res < 0 {
+            return Err(Error::from_errno(res as i32));
+        }
+        Ok(res as usize)
+    }
+
+    /// Writes `buf` into this file, starting at `offset`, without changing `offset`'s
+    /// caller-tracked value between calls.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write(&self, buf: &[u8], offset: u64) -> Result<usize> {
+        if self.flags() & flags::O_ACCMODE == flags::O_RDONLY {
+            return Err(EBADF);
+        }
+
+        let mut pos = offset as bindings::loff_t;
+        // SAFETY: `self.as_ptr()` is valid because the shared reference guarantees a nonzero
+        // refcount, and `buf` is valid for reading `buf.len()` bytes.
+        let res = unsafe {
+            bindings::kernel_write(self.as_ptr(), buf.as_ptr().cast(), buf.len(), &mut pos)
+        };
+        if res < 0 {
+            return Err(Error::from_errno(res as i32));
+        }
+        Ok(res as usize)
+    }
 }
 
 // SAFETY: The type invariants guarantee that `File` is always ref-counted.
@@ -293,6 +345,12 @@ impl DeferredFdCloser {
     ///
     /// Fails if this is called from a context where we cannot run work when returning to
     /// userspace. (E.g., from a kthread.)
+    ///
+    /// Note: there is no cheaper "fast path" for the common case where the fd turns out not to be
+    /// held via `fdget`. A refcount-based check cannot prove that, because on an unshared
+    /// `files_struct` `fdget`/`__fget_light` hands out a borrowed `struct file *` without taking
+    /// any extra reference at all, so `file_count() == 1` does not rule out a live borrow. Every
+    /// call pays for the allocation and `task_work_add` below.
     pub fn close_fd(self, fd: u32) -> Result<(), DeferredFdCloseError> {
         use bindings::task_work_notify_mode_TWA_RESUME as TWA_RESUME;
 
@@ -388,6 +446,164 @@ impl DeferredFdCloser {
     }
 }
 
+/// Helper used for closing a batch of file descriptors in a way that is safe even if the files
+/// are currently held using `fdget`.
+///
+/// This is the array-oriented counterpart to [`DeferredFdCloser`]: instead of scheduling one
+/// task work per fd, it schedules a single task work up front (in [`new`]) and then accumulates
+/// the files for several fds (e.g. a `BINDER_TYPE_FDA`) onto it via [`push_fd`].
+///
+/// Scheduling happens up front, before any fd is touched, precisely so that [`push_fd`] can close
+/// fds immediately: by construction, the eventual `fput` of every file it closes is already
+/// guaranteed to happen only after this task returns to userspace, which is what makes it safe
+/// even if a fd is currently held via `fdget`. Closing any fd before that guarantee is in place
+/// (e.g. deferring the `task_work_add` until a later `commit`, then synchronously `fput`-ing on
+/// failure or early drop) would reintroduce the same use-after-free that [`DeferredFdCloser`]
+/// exists to prevent.
+///
+/// # Invariants
+///
+/// `inner` points at a `DeferredFdArrayCloserInner` that has already been handed to a task work
+/// scheduled with `task_work_add` against `current`. That task work owns the allocation; this
+/// type only holds a pointer into it so that [`push_fd`] can keep appending files until
+/// [`commit`] relinquishes the handle.
+///
+/// [`new`]: Self::new
+/// [`push_fd`]: Self::push_fd
+/// [`commit`]: Self::commit
+pub struct DeferredFdArrayCloser {
+    inner: *mut DeferredFdArrayCloserInner,
+    current: *mut bindings::task_struct,
+    /// The task work scheduled in [`new`](Self::new) is tied to `current`, so `push_fd` must run
+    /// on that same task; this marker prevents the type from being sent to another one.
+    _not_send: NotThreadSafe,
+}
+
+#[repr(C)]
+struct DeferredFdArrayCloserInner {
+    twork: mem::MaybeUninit<bindings::callback_head>,
+    files: Vec<*mut bindings::file>,
+}
+
+impl DeferredFdArrayCloser {
+    /// Create a new, empty [`DeferredFdArrayCloser`] and schedule the task work that will
+    /// eventually `fput` everything pushed onto it.
+    ///
+    /// Fails if this is called from a context where we cannot run work when returning to
+    /// userspace. (E.g., from a kthread.)
+    pub fn new() -> Result<Self, DeferredFdCloseError> {
+        use bindings::task_work_notify_mode_TWA_RESUME as TWA_RESUME;
+
+        // SAFETY: Getting a pointer to current is always safe.
+        let current = unsafe { bindings::get_current() };
+
+        // SAFETY: Accessing the `flags` field of `current` is always safe.
+        let is_kthread = (unsafe { (*current).flags } & bindings::PF_KTHREAD) != 0;
+        if is_kthread {
+            return Err(DeferredFdCloseError::TaskWorkUnavailable);
+        }
+
+        let inner = Box::try_new(DeferredFdArrayCloserInner {
+            twork: mem::MaybeUninit::uninit(),
+            files: Vec::new(),
+        })
+        .map_err(|_| DeferredFdCloseError::TaskWorkUnavailable)?;
+
+        // This disables the destructor of the box; from here on, ownership of the allocation
+        // belongs to the task work we are about to schedule.
+        let inner = Box::into_raw(inner);
+
+        // The `callback_head` field is first in the struct, so this cast correctly gives us a
+        // pointer to the field.
+        let callback_head = inner.cast::<bindings::callback_head>();
+
+        // SAFETY: The `callback_head` pointer is compatible with the `do_close_fd_array` method.
+        unsafe { bindings::init_task_work(callback_head, Some(Self::do_close_fd_array)) };
+        // SAFETY: The `callback_head` pointer points at a valid and fully initialized task work
+        // that, once scheduled, owns the allocation and will `fput` every file later appended to
+        // its (currently empty) `files` list.
+        let res = unsafe { bindings::task_work_add(current, callback_head, TWA_RESUME) };
+
+        if res != 0 {
+            // SAFETY: Scheduling the task work failed, so we still have ownership of the box, so
+            // we may destroy it.
+            unsafe { drop(Box::from_raw(inner)) };
+
+            return Err(DeferredFdCloseError::TaskWorkUnavailable);
+        }
+
+        Ok(Self {
+            inner,
+            current,
+            _not_send: PhantomData,
+        })
+    }
+
+    /// Closes `fd` and adds it to the batch that the task work scheduled by [`new`] will `fput`.
+    ///
+    /// [`new`]: Self::new
+    pub fn push_fd(&mut self, fd: u32) -> Result<(), DeferredFdCloseError> {
+        // SAFETY: By the type invariants, `self.inner` was allocated by `new` and remains valid
+        // for as long as `self` exists.
+        let inner = unsafe { &mut *self.inner };
+
+        inner
+            .files
+            .try_reserve(1)
+            .map_err(|_| DeferredFdCloseError::TaskWorkUnavailable)?;
+
+        // SAFETY: Just an FFI call. This is safe no matter what `fd` is.
+        let file = unsafe { bindings::close_fd_get_file(fd) };
+        if file.is_null() {
+            return Err(DeferredFdCloseError::BadFd);
+        }
+
+        // SAFETY: The `file` pointer points at a valid file.
+        unsafe { bindings::get_file(file) };
+
+        // SAFETY: The task work scheduled in `new` only runs after this task returns to
+        // userspace, and any `fdget`-style borrow of this fd must be released before that point.
+        // So even if the current task holds such a borrow right now, the extra refcount we just
+        // took above guarantees the file stays alive until well after it is released.
+        //
+        // Note: fl_owner_t is currently a void pointer.
+        unsafe { bindings::filp_close(file, (*self.current).files as bindings::fl_owner_t) };
+
+        // This cannot fail, as we already reserved space for one more element above.
+        inner.files.push(file);
+
+        Ok(())
+    }
+
+    /// Finalizes the batch.
+    ///
+    /// The task work that will `fput` every file pushed with [`push_fd`] was already scheduled by
+    /// [`new`], so this only relinquishes the handle; nothing else needs to happen here, and
+    /// dropping a [`DeferredFdArrayCloser`] without calling this has the same effect.
+    ///
+    /// [`push_fd`]: Self::push_fd
+    /// [`new`]: Self::new
+    pub fn commit(self) {}
+
+    // SAFETY: This function is an implementation detail of `new`, so its safety comments should
+    // be read in extension of that method.
+    unsafe extern "C" fn do_close_fd_array(inner: *mut bindings::callback_head) {
+        // SAFETY: In `new` we schedule this method together with a pointer that originates from
+        // a `Box<DeferredFdArrayCloserInner>`, and we have just been given ownership of that
+        // allocation.
+        let inner = unsafe { Box::from_raw(inner as *mut DeferredFdArrayCloserInner) };
+        for &file in &inner.files {
+            // SAFETY: Every pointer in `files` was given an extra refcount by `push_fd`, which is
+            // dropped here. Since this callback runs in a task work after we return to
+            // userspace, it is guaranteed that the current thread doesn't hold these files with
+            // `fdget`, as `fdget` must be released before returning to userspace.
+            unsafe { bindings::fput(file) };
+        }
+        // Free the allocation.
+        drop(inner);
+    }
+}
+
 /// Represents a failure to close an fd in a deferred manner.
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum DeferredFdCloseError {
@@ -0,0 +1,70 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Linux Security Modules (LSM).
+//!
+//! C header: [`include/linux/security.h`](../../../../include/linux/security.h)
+
+use crate::{
+    bindings,
+    error::{to_result, Result},
+};
+
+/// A security context string.
+///
+/// # Invariants
+///
+/// The `secdata` and `seclen` fields correspond to a valid security context as returned by a
+/// successful call to `security_secid_to_secctx`, that has not yet been destroyed by calling
+/// `security_release_secctx`.
+pub struct SecurityCtx {
+    secdata: *mut core::ffi::c_char,
+    seclen: usize,
+}
+
+impl SecurityCtx {
+    /// Get the security context given its secid.
+    pub fn from_secid(secid: u32) -> Result<Self> {
+        let mut secdata = core::ptr::null_mut();
+        let mut seclen = 0u32;
+        // SAFETY: Just a C FFI call. The pointers are valid for writes.
+        let res = unsafe { bindings::security_secid_to_secctx(secid, &mut secdata, &mut seclen) };
+        to_result(res)?;
+
+        // INVARIANT: If the above call did not fail, then we have a valid security context and
+        // its length, and neither has been destroyed yet, since we only just obtained it.
+        Ok(Self {
+            secdata,
+            seclen: seclen as usize,
+        })
+    }
+
+    /// Returns the length of this security context.
+    pub fn len(&self) -> usize {
+        self.seclen
+    }
+
+    /// Returns whether this security context is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the bytes for this security context.
+    pub fn as_bytes(&self) -> &[u8] {
+        let ptr = self.secdata;
+        if ptr.is_null() {
+            return &[];
+        }
+
+        // SAFETY: The call to `security_secid_to_secctx` guarantees that `ptr` is valid for
+        // `self.seclen` bytes, and the context has not been released yet because we hold `self`.
+        unsafe { core::slice::from_raw_parts(ptr.cast(), self.seclen) }
+    }
+}
+
+impl Drop for SecurityCtx {
+    fn drop(&mut self) {
+        // SAFETY: By the type invariants, `self.secdata` and `self.seclen` store a valid security
+        // context obtained from `security_secid_to_secctx` that has not yet been released.
+        unsafe { bindings::security_release_secctx(self.secdata, self.seclen as u32) };
+    }
+}
@@ -101,3 +101,63 @@ impl PinnedDrop for PollCondVar {
         unsafe { bindings::synchronize_rcu() };
     }
 }
+
+/// Event bits used to build the bitmask returned by a [`Pollable::poll_mask`] implementation.
+///
+/// These mirror the `EPOLL*` constants from `include/uapi/linux/eventpoll.h`.
+pub mod events {
+    use crate::bindings;
+
+    /// The associated file is available for read operations.
+    pub const EPOLLIN: u32 = bindings::EPOLLIN;
+
+    /// The associated file is available for write operations.
+    pub const EPOLLOUT: u32 = bindings::EPOLLOUT;
+
+    /// Error condition happened on the associated file descriptor.
+    pub const EPOLLERR: u32 = bindings::EPOLLERR;
+
+    /// Hang up happened on the associated file descriptor.
+    pub const EPOLLHUP: u32 = bindings::EPOLLHUP;
+
+    /// Equivalent to [`EPOLLIN`], except with additional normal-priority data readiness.
+    pub const EPOLLRDNORM: u32 = bindings::EPOLLRDNORM;
+
+    /// Equivalent to [`EPOLLOUT`], except with additional normal-priority data readiness.
+    pub const EPOLLWRNORM: u32 = bindings::EPOLLWRNORM;
+}
+
+/// Implemented by drivers that want to participate in `poll`/`epoll` through a [`PollCondVar`].
+///
+/// Implementors only need to report which events are currently ready; registering with the
+/// [`PollTable`] and being re-polled when the condition variable is notified is handled by
+/// [`poll`].
+///
+/// # Incomplete
+///
+/// This only provides the helper half of `poll` support, not the epoll re-trigger path end to
+/// end: this crate does not yet have an `Operations`/`file_operations` vtable abstraction for
+/// Rust file implementations, so there is no `poll` slot for this trait to be wired into
+/// automatically, and nothing here has exercised a real epoll wait/wake cycle. Until the vtable
+/// exists, a driver's C `file_operations.poll` callback has to call [`poll`] itself, passing in
+/// the table it was given, and that wiring and the resulting wakeups remain untested.
+pub trait Pollable {
+    /// Returns the condition variable that should be registered with the [`PollTable`].
+    ///
+    /// Waking this condition variable (through its `Deref<Target = CondVar>` notify methods) is
+    /// what causes epoll to re-evaluate [`poll_mask`](Self::poll_mask).
+    fn poll_cond_var(&self) -> &PollCondVar;
+
+    /// Returns the current readiness mask, built from the constants in [`events`].
+    fn poll_mask(&self, file: &File) -> u32;
+}
+
+/// Helper for use from the `poll` callback of a `file_operations`.
+///
+/// This registers `table` with `obj`'s condition variable and then returns `obj`'s current
+/// readiness mask. It does not itself install anything into a `file_operations.poll` slot: the
+/// caller's callback is expected to forward its arguments to this function and return the result.
+pub fn poll<T: Pollable + ?Sized>(obj: &T, file: &File, table: &mut PollTable) -> u32 {
+    table.register_wait(file, obj.poll_cond_var());
+    obj.poll_mask(file)
+}
@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-2.0
+
+//! Kernel tasks and threads.
+//!
+//! C header: [`include/linux/sched.h`](../../../../include/linux/sched.h)
+
+use crate::bindings;
+
+/// Wraps the kernel's `struct kuid_t`.
+///
+/// # Invariants
+///
+/// The `kuid` field corresponds to a `kuid_t` as returned by the kernel. No additional
+/// invariants are imposed on it; in particular, it is not guaranteed to be valid in any
+/// particular user namespace.
+#[derive(Copy, Clone)]
+pub struct Kuid {
+    kuid: bindings::kuid_t,
+}
+
+impl Kuid {
+    /// Get the current euid.
+    pub fn current_euid() -> Self {
+        // SAFETY: Just an FFI call.
+        Self::from_raw(unsafe { bindings::current_euid() })
+    }
+
+    /// Create a `Kuid` given the raw C type.
+    pub fn from_raw(kuid: bindings::kuid_t) -> Self {
+        Self { kuid }
+    }
+
+    /// Turn this kuid into the raw C type.
+    pub fn into_raw(self) -> bindings::kuid_t {
+        self.kuid
+    }
+}
+
+impl PartialEq for Kuid {
+    fn eq(&self, other: &Kuid) -> bool {
+        // SAFETY: Just an FFI call. `kuid_t` comparisons must go through this helper because a
+        // `kuid_t` is only meaningful relative to a user namespace.
+        unsafe { bindings::uid_eq(self.kuid, other.kuid) }
+    }
+}
+
+impl Eq for Kuid {}
+
+/// Wraps the kernel's `struct kgid_t`.
+///
+/// # Invariants
+///
+/// The `kgid` field corresponds to a `kgid_t` as returned by the kernel. No additional
+/// invariants are imposed on it; in particular, it is not guaranteed to be valid in any
+/// particular user namespace.
+#[derive(Copy, Clone)]
+pub struct Kgid {
+    kgid: bindings::kgid_t,
+}
+
+impl Kgid {
+    /// Create a `Kgid` given the raw C type.
+    pub fn from_raw(kgid: bindings::kgid_t) -> Self {
+        Self { kgid }
+    }
+
+    /// Turn this kgid into the raw C type.
+    pub fn into_raw(self) -> bindings::kgid_t {
+        self.kgid
+    }
+}
+
+impl PartialEq for Kgid {
+    fn eq(&self, other: &Kgid) -> bool {
+        // SAFETY: Just an FFI call. `kgid_t` comparisons must go through this helper because a
+        // `kgid_t` is only meaningful relative to a user namespace.
+        unsafe { bindings::gid_eq(self.kgid, other.kgid) }
+    }
+}
+
+impl Eq for Kgid {}